@@ -0,0 +1,13 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Deserialize;
+
+/// Overrides for the host's resource limits, supplied by the caller so a
+/// simulation can be calibrated against a specific network's settings
+/// instead of the compiled-in defaults.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResourceCalibration {
+    pub cpu_instructions: Option<u64>,
+    pub memory_bytes: Option<u64>,
+}
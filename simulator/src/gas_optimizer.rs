@@ -0,0 +1,70 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::types::CostTypeUsage;
+use serde::Serialize;
+
+pub const CPU_LIMIT: u64 = 100_000_000;
+pub const MEMORY_LIMIT: u64 = 41_943_040;
+
+pub struct BudgetMetrics {
+    pub cpu_instructions: u64,
+    pub memory_bytes: u64,
+    pub total_operations: usize,
+    pub per_cost_type: Vec<CostTypeUsage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OptimizationReport {
+    pub suggestions: Vec<String>,
+    pub dominant_cost_type: Option<String>,
+}
+
+pub struct GasOptimizationAdvisor;
+
+impl GasOptimizationAdvisor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn analyze(&self, metrics: &BudgetMetrics) -> OptimizationReport {
+        let mut suggestions = Vec::new();
+
+        let cpu_usage_percent = (metrics.cpu_instructions as f64 / CPU_LIMIT as f64) * 100.0;
+        let memory_usage_percent = (metrics.memory_bytes as f64 / MEMORY_LIMIT as f64) * 100.0;
+
+        if cpu_usage_percent > 80.0 {
+            suggestions.push(format!(
+                "CPU usage at {:.1}% of limit; consider reducing contract call depth or loop iterations.",
+                cpu_usage_percent
+            ));
+        }
+        if memory_usage_percent > 80.0 {
+            suggestions.push(format!(
+                "Memory usage at {:.1}% of limit; consider smaller intermediate allocations.",
+                memory_usage_percent
+            ));
+        }
+        if metrics.total_operations == 0 {
+            suggestions.push("No operations were executed; nothing to optimize.".to_string());
+        }
+
+        let dominant = metrics
+            .per_cost_type
+            .iter()
+            .max_by_key(|c| c.cpu_insns)
+            .filter(|c| c.cpu_insns > 0);
+
+        if let Some(dominant) = dominant {
+            suggestions.push(format!(
+                "Dominant cost type is {} ({} cpu insns across {} iterations); focus optimization there first.",
+                dominant.name, dominant.cpu_insns, dominant.iterations
+            ));
+        }
+
+        OptimizationReport {
+            suggestions,
+            dominant_cost_type: dominant.map(|c| c.name.clone()),
+        }
+    }
+}
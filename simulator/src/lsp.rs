@@ -0,0 +1,180 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::source_mapper::{Frame, SourceLocation};
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Location {
+    pub uri: String,
+    pub range: Range,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelatedInformation {
+    pub location: Location,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub range: Range,
+    pub severity: u8,
+    pub message: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub related_information: Vec<RelatedInformation>,
+}
+
+fn file_uri(file: &str) -> String {
+    if file.contains("://") {
+        file.to_string()
+    } else {
+        format!("file://{}", file)
+    }
+}
+
+/// DWARF columns are 1-based with 0 reserved for "left edge of the line";
+/// LSP's `character` is always 0-based. `saturating_sub(1)` shifts real
+/// columns down by one and leaves the left-edge sentinel at 0.
+fn dwarf_column_to_lsp_character(column: u32) -> u32 {
+    column.saturating_sub(1)
+}
+
+fn location_to_range(loc: &SourceLocation) -> Range {
+    let line = loc.line.saturating_sub(1);
+    Range {
+        start: Position {
+            line,
+            character: dwarf_column_to_lsp_character(loc.column),
+        },
+        end: Position {
+            line,
+            character: dwarf_column_to_lsp_character(loc.column_end.unwrap_or(loc.column)),
+        },
+    }
+}
+
+/// Converts a resolved trap backtrace into LSP `Diagnostic` JSON objects
+/// grouped by file URI, so a trace can be fed straight into an editor or
+/// language-server client.
+///
+/// The innermost frame with a known source location becomes the primary
+/// diagnostic; the remaining frames become its `relatedInformation` spans.
+/// When `supports_related_information` is `false` (the client didn't
+/// advertise the capability), those spans are flattened into separate
+/// top-level diagnostics instead.
+pub fn frames_to_lsp_diagnostics(
+    frames: &[Frame],
+    supports_related_information: bool,
+) -> HashMap<String, Vec<Diagnostic>> {
+    let mut by_uri: HashMap<String, Vec<Diagnostic>> = HashMap::new();
+
+    let mut located = frames.iter().filter_map(|f| {
+        f.source_location
+            .as_ref()
+            .map(|loc| (f, loc))
+    });
+
+    let Some((head, head_loc)) = located.next() else {
+        return by_uri;
+    };
+
+    let related: Vec<RelatedInformation> = located
+        .map(|(frame, loc)| RelatedInformation {
+            location: Location {
+                uri: file_uri(&loc.file),
+                range: location_to_range(loc),
+            },
+            message: frame
+                .function_name
+                .clone()
+                .unwrap_or_else(|| "caller frame".to_string()),
+        })
+        .collect();
+
+    let head_uri = file_uri(&head_loc.file);
+    let head_message = format!(
+        "Contract trap at wasm offset 0x{:x}{}",
+        head.wasm_offset,
+        head.function_name
+            .as_ref()
+            .map(|n| format!(" in {}", n))
+            .unwrap_or_default()
+    );
+
+    if supports_related_information || related.is_empty() {
+        by_uri.entry(head_uri).or_default().push(Diagnostic {
+            range: location_to_range(head_loc),
+            severity: 1,
+            message: head_message,
+            related_information: related,
+        });
+    } else {
+        by_uri.entry(head_uri).or_default().push(Diagnostic {
+            range: location_to_range(head_loc),
+            severity: 1,
+            message: head_message,
+            related_information: vec![],
+        });
+        for r in related {
+            by_uri.entry(r.location.uri.clone()).or_default().push(Diagnostic {
+                range: r.location.range,
+                severity: 1,
+                message: format!("Caller frame: {}", r.message),
+                related_information: vec![],
+            });
+        }
+    }
+
+    by_uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_location_to_range_converts_to_zero_based_column() {
+        let loc = SourceLocation {
+            file: "lib.rs".to_string(),
+            line: 10,
+            column: 5,
+            column_end: Some(9),
+            source: None,
+        };
+
+        let range = location_to_range(&loc);
+        assert_eq!(range.start.character, 4);
+        assert_eq!(range.end.character, 8);
+    }
+
+    #[test]
+    fn test_location_to_range_left_edge_column_stays_zero() {
+        let loc = SourceLocation {
+            file: "lib.rs".to_string(),
+            line: 1,
+            column: 0,
+            column_end: None,
+            source: None,
+        };
+
+        let range = location_to_range(&loc);
+        assert_eq!(range.start.character, 0);
+        assert_eq!(range.end.character, 0);
+    }
+}
@@ -3,12 +3,13 @@
 
 mod config;
 mod gas_optimizer;
+mod lsp;
 mod runner;
 mod source_mapper;
 mod types;
 
 use crate::gas_optimizer::{BudgetMetrics, GasOptimizationAdvisor, CPU_LIMIT, MEMORY_LIMIT};
-use crate::source_mapper::SourceMapper;
+use crate::source_mapper::{SourceLocation, SourceMapper};
 use crate::types::*;
 use base64::Engine as _;
 use serde::{Deserialize, Serialize};
@@ -17,13 +18,80 @@ use soroban_env_host::{
     xdr::{HostFunction, Operation, OperationBody, ScVal},
     Host, HostError,
 };
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::env;
-use std::io::{self, Read};
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead, Read};
+use std::rc::Rc;
 use tracing_subscriber::{fmt, EnvFilter};
 
+/// Cache of already-parsed `SourceMapper`s, keyed by a fingerprint of the
+/// WASM bytes plus the embed-sources flag, so the daemon's module cache
+/// survives across requests instead of re-parsing debug info for a module
+/// it already built a mapper for.
+type MapperCache = HashMap<(u64, bool), Rc<SourceMapper>>;
+
+fn wasm_fingerprint(wasm_bytes: &[u8]) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    wasm_bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
 // Use types::SimulationRequest directly
 
+/// Panic metadata captured by [`install_panic_hook`], keyed to the thread
+/// that is about to run the simulation inside `catch_unwind`.
+struct CapturedPanic {
+    message: String,
+    location: Option<(String, u32, u32)>,
+    backtrace: Vec<String>,
+}
+
+thread_local! {
+    static CAPTURED_PANIC: RefCell<Option<CapturedPanic>> = RefCell::new(None);
+}
+
+/// Installs a panic hook that records the panic message, source location,
+/// and a captured backtrace into a thread-local, so the `catch_unwind`
+/// around contract execution can recover more than just the flat message
+/// `PanicInfo::payload()` exposes.
+fn install_panic_hook() {
+    std::panic::set_hook(Box::new(|info| {
+        let message = if let Some(s) = info.payload().downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = info.payload().downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "Unknown panic".to_string()
+        };
+
+        let location = info
+            .location()
+            .map(|loc| (loc.file().to_string(), loc.line(), loc.column()));
+
+        let backtrace = std::backtrace::Backtrace::force_capture()
+            .to_string()
+            .lines()
+            .map(|line| line.to_string())
+            .collect();
+
+        CAPTURED_PANIC.with(|cell| {
+            *cell.borrow_mut() = Some(CapturedPanic {
+                message,
+                location,
+                backtrace,
+            });
+        });
+    }));
+}
+
+/// Takes the most recently captured panic off the thread-local, leaving it
+/// empty for the next run.
+fn take_captured_panic() -> Option<CapturedPanic> {
+    CAPTURED_PANIC.with(|cell| cell.borrow_mut().take())
+}
+
 fn init_logger() {
     // Check if the environment variable ERST_LOG_FORMAT is set to "json"
     let use_json = env::var("ERST_LOG_FORMAT")
@@ -46,32 +114,72 @@ fn init_logger() {
     }
 }
 
-fn send_error(msg: String) {
-    let res = SimulationResponse {
-        status: "error".to_string(),
-        error: Some(msg),
-        events: vec![],
-        diagnostic_events: vec![],
-        categorized_events: vec![],
-        logs: vec![],
-        flamegraph: None,
-        optimization_report: None,
-        budget_usage: None,
-        source_location: None,
-        wasm_offset: None,
-    };
-    println!("{}", serde_json::to_string(&res).unwrap());
-    std::process::exit(1);
+/// Failure from running a transaction's operations: either a typed Soroban
+/// `HostError` (a VM trap, budget exhaustion, etc.), or a host-signalled
+/// abort recovered from a host function that unwound with a boxed payload
+/// instead of returning a `HostError`.
+enum ExecError {
+    Host(HostError),
+    Abort(HostAbort),
 }
 
-fn execute_operations(host: &Host, operations: &[Operation]) -> Result<Vec<String>, HostError> {
+impl From<HostError> for ExecError {
+    fn from(err: HostError) -> Self {
+        ExecError::Host(err)
+    }
+}
+
+/// Attempts to downcast a caught host-function panic payload into a known
+/// abort shape, falling back to an "Unknown" type name when the payload
+/// doesn't match anything we recognize.
+fn decode_host_abort(payload: Box<dyn std::any::Any + Send>) -> HostAbort {
+    if let Some(exit) = payload.downcast_ref::<ExitCode>() {
+        HostAbort {
+            code: Some(exit.0),
+            type_name: "ExitCode".to_string(),
+            message: None,
+        }
+    } else if let Some(code) = payload.downcast_ref::<i32>() {
+        HostAbort {
+            code: Some(*code),
+            type_name: "i32".to_string(),
+            message: None,
+        }
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        HostAbort {
+            code: None,
+            type_name: "String".to_string(),
+            message: Some(msg.clone()),
+        }
+    } else if let Some(msg) = payload.downcast_ref::<&str>() {
+        HostAbort {
+            code: None,
+            type_name: "&str".to_string(),
+            message: Some(msg.to_string()),
+        }
+    } else {
+        HostAbort {
+            code: None,
+            type_name: "Unknown".to_string(),
+            message: None,
+        }
+    }
+}
+
+fn execute_operations(host: &Host, operations: &[Operation]) -> Result<Vec<String>, ExecError> {
     let mut logs = Vec::new();
     for op in operations {
         match &op.body {
             OperationBody::InvokeHostFunction(invoke_op) => {
                 logs.push("Executing InvokeHostFunction...".to_string());
-                let val = host.invoke_function(invoke_op.host_function.clone())?;
-                logs.push(format!("Result: {:?}", val));
+                let call = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    host.invoke_function(invoke_op.host_function.clone())
+                }));
+                match call {
+                    Ok(Ok(val)) => logs.push(format!("Result: {:?}", val)),
+                    Ok(Err(host_err)) => return Err(ExecError::Host(host_err)),
+                    Err(abort_payload) => return Err(ExecError::Abort(decode_host_abort(abort_payload))),
+                }
             }
             _ => {
                 logs.push(format!(
@@ -142,49 +250,83 @@ fn main() {
     // 1. Initialize the logger immediately
     init_logger();
 
+    // 1b. Install the panic hook before any simulation runs so a panic
+    // inside `catch_unwind` leaves structured metadata behind.
+    install_panic_hook();
+
     // 2. Log that we started
     tracing::info!(event = "simulator_started", "Simulator initializing...");
 
+    let args: Vec<String> = env::args().collect();
+
+    if args.iter().any(|a| a == "--daemon") {
+        run_daemon();
+        return;
+    }
+
+    let format = args
+        .iter()
+        .zip(args.iter().skip(1))
+        .find(|(flag, _)| *flag == "--format")
+        .map(|(_, value)| value.as_str());
+
+    match format {
+        Some("source-map") => {
+            run_source_map_mode();
+            return;
+        }
+        Some("lsp") => {
+            run_lsp_mode();
+            return;
+        }
+        _ => {}
+    }
+
     // Read JSON from Stdin
     let mut buffer = String::new();
     if let Err(e) = io::stdin().read_to_string(&mut buffer) {
-        let res = SimulationResponse {
-            status: "error".to_string(),
-            error: Some(format!("Failed to read stdin: {}", e)),
-            events: vec![],
-            diagnostic_events: vec![],
-            categorized_events: vec![],
-            logs: vec![],
-            flamegraph: None,
-            optimization_report: None,
-            budget_usage: None,
-            source_location: None,
-        };
-        println!("{}", serde_json::to_string(&res).unwrap());
         eprintln!("Failed to read stdin: {}", e);
+        let res = build_error_response(format!("Failed to read stdin: {}", e));
+        println!("{}", serde_json::to_string(&res).unwrap());
         return;
     }
 
+    let mut mapper_cache = MapperCache::new();
+    let response = simulate_one(&buffer, &mut mapper_cache);
+    println!("{}", serde_json::to_string(&response).unwrap());
+}
+
+/// Builds an error `SimulationResponse` from a single message, for the
+/// early-exit failure points that don't have enough context yet to fill in
+/// the rest of the struct.
+fn build_error_response(msg: String) -> SimulationResponse {
+    SimulationResponse {
+        status: "error".to_string(),
+        error: Some(msg),
+        events: vec![],
+        diagnostic_events: vec![],
+        categorized_events: vec![],
+        logs: vec![],
+        flamegraph: None,
+        optimization_report: None,
+        budget_usage: None,
+        source_location: None,
+        wasm_offset: None,
+        panic_backtrace: None,
+        backtrace: vec![],
+        host_error: None,
+        trap_code: None,
+    }
+}
+
+/// Runs a single simulation from a raw JSON request body through to a
+/// `SimulationResponse`. Shared by the single-shot and daemon entry points
+/// so both exercise exactly the same path.
+fn simulate_one(buffer: &str, mapper_cache: &mut MapperCache) -> SimulationResponse {
     // Parse Request
-    let request: SimulationRequest = match serde_json::from_str(&buffer) {
+    let request: SimulationRequest = match serde_json::from_str(buffer) {
         Ok(req) => req,
-        Err(e) => {
-            let res = SimulationResponse {
-                status: "error".to_string(),
-                error: Some(format!("Invalid JSON: {}", e)),
-                events: vec![],
-                diagnostic_events: vec![],
-                categorized_events: vec![],
-                logs: vec![],
-                flamegraph: None,
-                optimization_report: None,
-                budget_usage: None,
-                source_location: None,
-                wasm_offset: None,
-            };
-            println!("{}", serde_json::to_string(&res).expect("Failed to serialize error response"));
-            return;
-        }
+        Err(e) => return build_error_response(format!("Invalid JSON: {}", e)),
     };
 
     // Decode Envelope XDR
@@ -194,15 +336,9 @@ fn main() {
             soroban_env_host::xdr::Limits::none(),
         ) {
             Ok(env) => env,
-            Err(e) => {
-                send_error(format!("Failed to parse Envelope XDR: {}", e));
-                return;
-            }
+            Err(e) => return build_error_response(format!("Failed to parse Envelope XDR: {}", e)),
         },
-        Err(e) => {
-            send_error(format!("Failed to decode Envelope Base64: {}", e));
-            return;
-        }
+        Err(e) => return build_error_response(format!("Failed to decode Envelope Base64: {}", e)),
     };
 
     // Decode ResultMeta XDR
@@ -240,11 +376,24 @@ fn main() {
         }
     };
 
-    // Initialize source mapper if WASM is provided
-    let source_mapper = if let Some(wasm_base64) = &request.contract_wasm {
+    // Initialize source mapper if WASM is provided, reusing an already-built
+    // mapper from `mapper_cache` when this module was seen before so the
+    // daemon doesn't re-walk DWARF line programs on every request.
+    let source_mapper: Option<Rc<SourceMapper>> = if let Some(wasm_base64) = &request.contract_wasm
+    {
         match base64::engine::general_purpose::STANDARD.decode(wasm_base64) {
             Ok(wasm_bytes) => {
-                let mapper = SourceMapper::new(wasm_bytes);
+                let key = (wasm_fingerprint(&wasm_bytes), request.embed_source_snippets);
+                let embed_source_snippets = request.embed_source_snippets;
+                let mapper = mapper_cache
+                    .entry(key)
+                    .or_insert_with(|| {
+                        Rc::new(SourceMapper::new_with_options(
+                            wasm_bytes,
+                            embed_source_snippets,
+                        ))
+                    })
+                    .clone();
                 if mapper.has_debug_symbols() {
                     eprintln!("Debug symbols found in WASM");
                     Some(mapper)
@@ -279,13 +428,11 @@ fn main() {
                 ) {
                     Ok(k) => k,
                     Err(e) => {
-                        send_error(format!("Failed to parse LedgerKey XDR: {}", e));
-                        return;
+                        return build_error_response(format!("Failed to parse LedgerKey XDR: {}", e))
                     }
                 },
                 Err(e) => {
-                    send_error(format!("Failed to decode LedgerKey Base64: {}", e));
-                    return;
+                    return build_error_response(format!("Failed to decode LedgerKey Base64: {}", e))
                 }
             };
 
@@ -297,13 +444,14 @@ fn main() {
                 ) {
                     Ok(e) => e,
                     Err(e) => {
-                        send_error(format!("Failed to parse LedgerEntry XDR: {}", e));
-                        return;
+                        return build_error_response(format!("Failed to parse LedgerEntry XDR: {}", e))
                     }
                 },
                 Err(e) => {
-                    send_error(format!("Failed to decode LedgerEntry Base64: {}", e));
-                    return;
+                    return build_error_response(format!(
+                        "Failed to decode LedgerEntry Base64: {}",
+                        e
+                    ))
                 }
             };
 
@@ -335,7 +483,11 @@ fn main() {
 
     let cpu_usage_percent = (cpu_insns as f64 / CPU_LIMIT as f64) * 100.0;
     let memory_usage_percent = (mem_bytes as f64 / MEMORY_LIMIT as f64) * 100.0;
+    let per_cost_type = collect_per_cost_type_usage(&budget);
 
+    // `reclaimed_pages` is filled in once `host` is dropped below, after
+    // every branch that still needs it (event/backtrace extraction) has
+    // run.
     let budget_usage = BudgetUsage {
         cpu_instructions: cpu_insns,
         memory_bytes: mem_bytes,
@@ -344,6 +496,8 @@ fn main() {
         memory_limit: MEMORY_LIMIT,
         cpu_usage_percent,
         memory_usage_percent,
+        per_cost_type,
+        reclaimed_pages: 0,
     };
 
     let optimization_report = if request.enable_optimization_advisor {
@@ -352,6 +506,7 @@ fn main() {
             cpu_instructions: budget_usage.cpu_instructions,
             memory_bytes: budget_usage.memory_bytes,
             total_operations: budget_usage.operations_count,
+            per_cost_type: budget_usage.per_cost_type.clone(),
         };
         Some(advisor.analyze(&metrics))
     } else {
@@ -360,8 +515,18 @@ fn main() {
 
     let mut flamegraph_svg = None;
     if request.profile.unwrap_or(false) {
-        // Simple simulated flamegraph for demonstration
-        let folded_data = format!("Total;CPU {}\nTotal;Memory {}\n", cpu_insns, mem_bytes);
+        let mut folded_data = String::new();
+        for usage in &budget_usage.per_cost_type {
+            if usage.cpu_insns > 0 {
+                folded_data.push_str(&format!("Total;{} {}\n", usage.name, usage.cpu_insns));
+            }
+        }
+        for usage in &budget_usage.per_cost_type {
+            if usage.mem_bytes > 0 {
+                folded_data.push_str(&format!("Memory;{} {}\n", usage.name, usage.mem_bytes));
+            }
+        }
+
         let mut result_vec = Vec::new();
         let mut options = inferno::flamegraph::Options::default();
         options.title = "Soroban Resource Consumption".to_string();
@@ -375,7 +540,7 @@ fn main() {
         }
     }
 
-    match result {
+    let mut response = match result {
         Ok(Ok(exec_logs)) => {
             // Extract both raw event strings and structured diagnostic events
             let (events, diagnostic_events): (Vec<String>, Vec<DiagnosticEvent>) =
@@ -458,10 +623,15 @@ fn main() {
                 budget_usage: Some(budget_usage),
                 source_location: None,
                 wasm_offset: None,
+                panic_backtrace: None,
+                backtrace: vec![],
+                host_error: None,
+                trap_code: None,
             };
 
-            println!("{}", serde_json::to_string(&response).unwrap());
-        Ok(Err(host_error)) => {
+            response
+        }
+        Ok(Err(ExecError::Host(host_error))) => {
             // Host error during execution (e.g., contract trap, validation failure)
 
             // Extract both raw event strings and structured diagnostic events
@@ -577,14 +747,21 @@ fn main() {
             };
 
             let error_msg = format!("{:?}", host_error);
-            let wasm_offset = extract_wasm_offset(&error_msg);
-            
+            let wasm_offsets = extract_wasm_offsets(&error_msg);
+            let wasm_offset = wasm_offsets.first().copied();
+            let trap_code = decode_error(&error_msg);
+
             let source_location = if let (Some(offset), Some(mapper)) = (wasm_offset, &source_mapper) {
                 mapper.map_wasm_offset_to_source(offset)
             } else {
                 None
             };
 
+            let backtrace = source_mapper
+                .as_ref()
+                .map(|mapper| mapper.resolve_backtrace(&wasm_offsets))
+                .unwrap_or_default();
+
             let response = SimulationResponse {
                 status: "error".to_string(),
                 error: Some(serde_json::to_string(&structured_error).unwrap()),
@@ -597,47 +774,232 @@ fn main() {
                 budget_usage: None,
                 source_location,
                 wasm_offset,
+                panic_backtrace: None,
+                backtrace,
+                host_error: None,
+                trap_code: Some(trap_code),
             };
-            println!("{}", serde_json::to_string(&response).unwrap());
+
+            response
         }
-        Err(panic_info) => {
-            let panic_msg = if let Some(s) = panic_info.downcast_ref::<&str>() {
-                s.to_string()
-            } else if let Some(s) = panic_info.downcast_ref::<String>() {
-                s.clone()
-            } else {
-                "Unknown panic".to_string()
+        Ok(Err(ExecError::Abort(host_abort))) => {
+            // The host function unwound with a payload that
+            // `decode_host_abort` downcast into a `HostAbort`, so we keep
+            // that structure instead of collapsing it into `error: String`.
+            let categorized_events = vec![CategorizedEvent {
+                category: "HostAbort".to_string(),
+                event: DiagnosticEvent {
+                    event_type: "host_abort".to_string(),
+                    contract_id: None,
+                    topics: vec![],
+                    data: format!("{:?}", host_abort),
+                    in_successful_contract_call: false,
+                },
+            }];
+
+            let response = SimulationResponse {
+                status: "error".to_string(),
+                error: Some(format!(
+                    "Host function aborted ({}): {:?}",
+                    host_abort.type_name, host_abort.code
+                )),
+                events: vec![],
+                diagnostic_events: vec![],
+                categorized_events,
+                logs: vec![],
+                flamegraph: None,
+                optimization_report: None,
+                budget_usage: None,
+                source_location: None,
+                wasm_offset: None,
+                panic_backtrace: None,
+                backtrace: vec![],
+                host_error: Some(host_abort),
+                trap_code: None,
             };
 
+            response
+        }
+        Err(_panic_payload) => {
+            let captured = take_captured_panic();
+
+            let panic_msg = captured
+                .as_ref()
+                .map(|c| c.message.clone())
+                .unwrap_or_else(|| "Unknown panic".to_string());
+
+            let source_location = captured.as_ref().and_then(|c| {
+                c.location.as_ref().map(|(file, line, column)| SourceLocation {
+                    file: file.clone(),
+                    line: *line,
+                    column: *column,
+                    column_end: None,
+                    source: None,
+                })
+            });
+
+            let mut logs = vec![format!("PANIC: {}", panic_msg)];
+            if let Some((file, line, column)) =
+                captured.as_ref().and_then(|c| c.location.clone())
+            {
+                logs.push(format!("PANIC location: {}:{}:{}", file, line, column));
+            }
+
             let response = SimulationResponse {
                 status: "error".to_string(),
                 error: Some(format!("Simulator panicked: {}", panic_msg)),
                 events: vec![],
                 diagnostic_events: vec![],
                 categorized_events: vec![],
-                logs: vec![format!("PANIC: {}", panic_msg)],
+                logs,
                 flamegraph: None,
                 optimization_report: None,
                 budget_usage: None,
-                source_location: None,
+                source_location,
                 wasm_offset: None,
+                panic_backtrace: captured.map(|c| c.backtrace),
+                backtrace: vec![],
+                host_error: None,
+                trap_code: None,
             };
-            println!("{}", serde_json::to_string(&response).unwrap());
+
+            response
+        }
+    };
+
+    // Every branch above has pulled what it needs out of `host` (events,
+    // backtraces, budget); dropping it here releases its WASM instance's
+    // linear memory and VM bookkeeping back to the allocator, and trimming
+    // the allocator hands bulk-freed heap back to the OS, so peak RSS stays
+    // bounded when the daemon runs thousands of requests back to back.
+    let reclaimed_pages = runner::decommit_memory(host);
+    if let Some(budget_usage) = response.budget_usage.as_mut() {
+        budget_usage.reclaimed_pages = reclaimed_pages;
+    }
+
+    response
+}
+
+/// Runs one `SimulationRequest` per newline-delimited line read from stdin,
+/// emitting one `SimulationResponse` per line. This avoids paying process
+/// startup cost per simulation when a caller fires many of them back to
+/// back. A `MapperCache` lives for the whole loop so a module seen on an
+/// earlier line doesn't get re-parsed, and `simulate_one` drops and trims
+/// its `Host` after each run so peak RSS stays bounded across thousands of
+/// requests.
+fn run_daemon() {
+    let stdin = io::stdin();
+    let mut mapper_cache = MapperCache::new();
+    for line in stdin.lock().lines() {
+        let line = match line {
+            Ok(l) => l,
+            Err(e) => {
+                eprintln!("Failed to read line from stdin: {}", e);
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = simulate_one(&line, &mut mapper_cache);
+        println!("{}", serde_json::to_string(&response).unwrap());
+    }
+}
+
+/// Reads a `SimulationRequest` from stdin and prints its module's Source
+/// Map v3 document instead of running the simulation, for `--format
+/// source-map`.
+fn run_source_map_mode() {
+    let mut buffer = String::new();
+    if let Err(e) = io::stdin().read_to_string(&mut buffer) {
+        eprintln!("Failed to read stdin: {}", e);
+        return;
+    }
+
+    let request: SimulationRequest = match serde_json::from_str(&buffer) {
+        Ok(req) => req,
+        Err(e) => {
+            eprintln!("Invalid JSON: {}", e);
+            return;
+        }
+    };
+
+    let Some(wasm_base64) = &request.contract_wasm else {
+        eprintln!("No contract_wasm provided; cannot build a source map");
+        return;
+    };
+
+    let wasm_bytes = match base64::engine::general_purpose::STANDARD.decode(wasm_base64) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("Failed to decode WASM base64: {}", e);
+            return;
         }
+    };
+
+    let mapper = SourceMapper::new(wasm_bytes);
+    println!("{}", mapper.to_source_map_v3());
+}
+
+/// Runs a full simulation and prints its resolved trap backtrace as LSP
+/// `Diagnostic` JSON, grouped by file URI, for `--format lsp`.
+///
+/// `related_information_capable` mirrors the client capability a real
+/// language server would read off the `initialize` request; until this
+/// simulator takes one, it's hardcoded to the common case.
+fn run_lsp_mode() {
+    let mut buffer = String::new();
+    if let Err(e) = io::stdin().read_to_string(&mut buffer) {
+        eprintln!("Failed to read stdin: {}", e);
+        return;
     }
+
+    let mut mapper_cache = MapperCache::new();
+    let response = simulate_one(&buffer, &mut mapper_cache);
+    let related_information_capable = true;
+    let diagnostics =
+        lsp::frames_to_lsp_diagnostics(&response.backtrace, related_information_capable);
+    println!("{}", serde_json::to_string(&diagnostics).unwrap());
+}
+
+/// Walks every `ContractCostType` variant and reads the cloned budget's
+/// per-type tracker, so callers get a real resource-attribution breakdown
+/// instead of just the aggregate CPU/memory totals.
+fn collect_per_cost_type_usage(
+    budget: &soroban_env_host::budget::Budget,
+) -> Vec<CostTypeUsage> {
+    soroban_env_host::xdr::ContractCostType::variants()
+        .iter()
+        .filter_map(|cost_type| {
+            let model = budget.get_tracker(*cost_type).ok()?;
+            Some(CostTypeUsage {
+                name: format!("{:?}", cost_type),
+                cpu_insns: model.cpu,
+                mem_bytes: model.mem,
+                iterations: model.iterations,
+            })
+        })
+        .collect()
 }
 
-fn extract_wasm_offset(error_msg: &str) -> Option<u64> {
+/// Parses every `@ 0x…` frame offset out of a trap message, in the order
+/// they appear (innermost frame first), instead of just the first one.
+fn extract_wasm_offsets(error_msg: &str) -> Vec<u64> {
+    let mut offsets = Vec::new();
     for line in error_msg.lines() {
-        if let Some(pos) = line.find("@ 0x") {
-            let hex_part = &line[pos + 4..];
-            let end = hex_part.find(|c: char| !c.is_ascii_hexdigit()).unwrap_or(hex_part.len());
+        let mut rest = line;
+        while let Some(pos) = rest.find("@ 0x") {
+            let hex_part = &rest[pos + 4..];
+            let end = hex_part
+                .find(|c: char| !c.is_ascii_hexdigit())
+                .unwrap_or(hex_part.len());
             if let Ok(offset) = u64::from_str_radix(&hex_part[..end], 16) {
-                return Some(offset);
+                offsets.push(offset);
             }
+            rest = &hex_part[end..];
         }
     }
-    None
+    offsets
 }
 
 
@@ -647,7 +1009,8 @@ mod tests {
 
     #[test]
     fn test_decode_vm_traps() {
-        let msg = decode_error("Error: Wasm Trap: out of bounds memory access");
-        assert!(msg.contains("VM Trap: Out of Bounds Access"));
+        let trap = decode_error("Error: Wasm Trap: out of bounds memory access");
+        assert_eq!(trap, TrapCode::OutOfBoundsMemoryAccess);
+        assert!(trap.to_string().contains("VM Trap: Out of Bounds Access"));
     }
 }
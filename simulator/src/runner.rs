@@ -0,0 +1,84 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::ResourceCalibration;
+use soroban_env_host::{budget::Budget, Host};
+
+/// Thin wrapper around `soroban_env_host::Host` that applies optional
+/// resource calibration before handing the host back to the caller.
+pub struct SimHost {
+    pub inner: Host,
+}
+
+impl SimHost {
+    pub fn new(budget: Option<Budget>, calibration: Option<ResourceCalibration>) -> Self {
+        let host = match budget {
+            Some(b) => Host::with_budget(b),
+            None => Host::default(),
+        };
+
+        if let Some(calibration) = calibration {
+            let budget = host.budget_cloned();
+            if let Some(cpu) = calibration.cpu_instructions {
+                let _ = budget.reset_limits(cpu, calibration.memory_bytes.unwrap_or(u64::MAX));
+            } else if let Some(mem) = calibration.memory_bytes {
+                let _ = budget.reset_limits(u64::MAX, mem);
+            }
+        }
+
+        Self { inner: host }
+    }
+}
+
+/// Reads this process's current resident set size, in 4 KiB pages, from
+/// procfs. `Host` doesn't expose its instance's linear memory as a public
+/// API, so we can't `madvise` it directly; measuring RSS before and after
+/// dropping the host is the honest substitute for a page count we can
+/// actually observe. Returns `None` off Linux or if `/proc` isn't mounted.
+fn resident_pages() -> Option<u64> {
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    statm.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Drops `host`, releasing every allocation its run made (the WASM
+/// instance's linear memory, VM bookkeeping, event buffers, ...), then asks
+/// the allocator to hand any bulk-freed heap back to the OS. Intended to
+/// run between requests in the daemon's loop so memory grown by one
+/// simulation doesn't let peak RSS grow unbounded across thousands of runs.
+/// Returns a best-effort process RSS delta in pages, measured from procfs
+/// before and after -- this is a whole-process reading, not a targeted
+/// count of `host`'s own pages, so concurrent allocations on other threads
+/// show up in it too.
+///
+/// On Linux, `malloc_trim(3)` performs the actual release; elsewhere
+/// dropping `host` still frees the allocations, just without a syscall to
+/// hand pages back to the OS, so this returns 0.
+pub fn decommit_memory(host: Host) -> u64 {
+    let before = resident_pages();
+    drop(host);
+
+    #[cfg(target_os = "linux")]
+    unsafe {
+        libc::malloc_trim(0);
+    }
+
+    let after = resident_pages();
+    match (before, after) {
+        (Some(before), Some(after)) => before.saturating_sub(after),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decommit_memory_drops_host_without_panicking() {
+        let sim_host = SimHost::new(None, None);
+        // Mostly exercises that dropping the host and trimming the
+        // allocator doesn't panic; the actual page count is environment
+        // dependent (procfs may be unavailable, e.g. in some sandboxes).
+        let _reclaimed_pages = decommit_memory(sim_host.inner);
+    }
+}
@@ -1,11 +1,38 @@
 // Copyright 2025 Erst Users
 // SPDX-License-Identifier: Apache-2.0
 
-use object::Object;
+use gimli::{EndianSlice, LittleEndian};
+use object::{Object, ObjectSection};
 use serde::Serialize;
+use std::borrow::Cow;
 
 pub struct SourceMapper {
     has_symbols: bool,
+    /// Line-program rows across all compilation units, sorted by address,
+    /// so a lookup can binary-search for the row covering a given offset.
+    rows: Vec<LineRow>,
+    /// `(start_address, name)` pairs parsed from the WASM `name` custom
+    /// section, sorted by address, so a trap offset can be attributed to
+    /// the function that contains it even when DWARF info is absent.
+    functions: Vec<(u64, String)>,
+    /// Kept around so embedded-source snippets can be decoded lazily, only
+    /// for offsets actually mapped, instead of eagerly at construction.
+    wasm_bytes: Vec<u8>,
+    /// Whether to look for a DWARFv5 embedded-source attribute when
+    /// resolving a source location. Off by default since scanning for it
+    /// is only worth the cost when a caller actually wants snippets.
+    embed_sources: bool,
+}
+
+/// One row of a resolved DWARF line-number program: the wasm-code-section
+/// address it covers, the source position it maps to, and whether it
+/// closes out a sequence (so we don't map past the end of a function).
+struct LineRow {
+    address: u64,
+    file: String,
+    line: u32,
+    column: u32,
+    end_sequence: bool,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -14,12 +41,45 @@ pub struct SourceLocation {
     pub line: u32,
     pub column: u32,
     pub column_end: Option<u32>,
+    /// A few lines of context around `line`, decoded from a DWARFv5
+    /// embedded-source attribute when the module carries one and the
+    /// caller opted into embedding (see [`SourceMapper::new_with_options`]).
+    pub source: Option<String>,
+}
+
+/// One resolved frame of a symbolicated trap backtrace: the raw wasm
+/// offset plus whatever name/source info we could recover for it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Frame {
+    pub wasm_offset: u64,
+    pub function_name: Option<String>,
+    pub source_location: Option<SourceLocation>,
 }
 
 impl SourceMapper {
     pub fn new(wasm_bytes: Vec<u8>) -> Self {
+        Self::new_with_options(wasm_bytes, false)
+    }
+
+    /// Like [`SourceMapper::new`], but lets the caller opt into decoding
+    /// DWARFv5 embedded-source snippets for mapped offsets. Left off by
+    /// default since most callers never look at `SourceLocation::source`
+    /// and decoding it is pure overhead for them.
+    pub fn new_with_options(wasm_bytes: Vec<u8>, embed_sources: bool) -> Self {
         let has_symbols = Self::check_debug_symbols(&wasm_bytes);
-        Self { has_symbols }
+        let rows = if has_symbols {
+            Self::parse_line_rows(&wasm_bytes).unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let functions = Self::parse_functions(&wasm_bytes);
+        Self {
+            has_symbols,
+            rows,
+            functions,
+            wasm_bytes,
+            embed_sources,
+        }
     }
 
     fn check_debug_symbols(wasm_bytes: &[u8]) -> bool {
@@ -32,24 +92,299 @@ impl SourceMapper {
         }
     }
 
-    pub fn map_wasm_offset_to_source(&self, _wasm_offset: u64) -> Option<SourceLocation> {
-        if !self.has_symbols {
+    /// Walks the `.debug_line` program for every compilation unit in
+    /// `.debug_info`, collecting `(address, file, line, column)` rows
+    /// sorted by address so `map_wasm_offset_to_source` can binary-search
+    /// them.
+    fn parse_line_rows(wasm_bytes: &[u8]) -> Option<Vec<LineRow>> {
+        let obj = object::File::parse(wasm_bytes).ok()?;
+
+        let load_section = |id: gimli::SectionId| -> Result<Cow<[u8]>, gimli::Error> {
+            Ok(obj
+                .section_by_name(id.name())
+                .and_then(|section| section.uncompressed_data().ok())
+                .unwrap_or(Cow::Borrowed(&[])))
+        };
+        let dwarf_cow = gimli::Dwarf::load(load_section).ok()?;
+        let dwarf = dwarf_cow.borrow(|section| EndianSlice::new(section, LittleEndian));
+
+        let mut rows = Vec::new();
+        let mut unit_headers = dwarf.units();
+        while let Ok(Some(header)) = unit_headers.next() {
+            let Ok(unit) = dwarf.unit(header) else {
+                continue;
+            };
+            let Some(line_program) = unit.line_program.clone() else {
+                continue;
+            };
+
+            let mut line_rows = line_program.rows();
+            while let Ok(Some((header, row))) = line_rows.next_row() {
+                let file = row
+                    .file(header)
+                    .and_then(|f| dwarf.attr_string(&unit, f.path_name()).ok())
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "<unknown>".to_string());
+
+                let column = match row.column() {
+                    gimli::ColumnType::LeftEdge => 0,
+                    gimli::ColumnType::Column(c) => c.get() as u32,
+                };
+
+                rows.push(LineRow {
+                    address: row.address(),
+                    file,
+                    line: row.line().map(|l| l.get() as u32).unwrap_or(0),
+                    column,
+                    end_sequence: row.end_sequence(),
+                });
+            }
+        }
+
+        rows.sort_by_key(|r| r.address);
+        Some(rows)
+    }
+
+    /// Reads function names out of the WASM `name` custom section via
+    /// `object`'s symbol table, sorted by start address so
+    /// `resolve_function_name` can binary-search for the function covering
+    /// an offset. Returns an empty list for modules built without a `name`
+    /// section rather than failing the whole mapper.
+    fn parse_functions(wasm_bytes: &[u8]) -> Vec<(u64, String)> {
+        let Ok(obj) = object::File::parse(wasm_bytes) else {
+            return Vec::new();
+        };
+
+        let mut functions: Vec<(u64, String)> = obj
+            .symbols()
+            .filter(|sym| sym.kind() == object::SymbolKind::Text)
+            .filter_map(|sym| sym.name().ok().map(|name| (sym.address(), name.to_string())))
+            .collect();
+        functions.sort_by_key(|(address, _)| *address);
+        functions
+    }
+
+    /// Binary-searches the resolved line rows for the greatest address
+    /// `<= wasm_offset`, respecting `end_sequence` rows so a trap that
+    /// happened past the last known instruction of a function doesn't get
+    /// attributed to it. Returns `None` when the offset falls in a gap or
+    /// before the first row.
+    pub fn map_wasm_offset_to_source(&self, wasm_offset: u64) -> Option<SourceLocation> {
+        if !self.has_symbols || self.rows.is_empty() {
+            return None;
+        }
+
+        let idx = match self
+            .rows
+            .binary_search_by(|row| row.address.cmp(&wasm_offset))
+        {
+            Ok(i) => i,
+            Err(0) => return None,
+            Err(i) => i - 1,
+        };
+
+        let row = &self.rows[idx];
+        if row.end_sequence {
             return None;
         }
 
-        // For demonstration purposes, simulate mapping
-        // In a real implementation, this would use addr2line or similar
+        let column_end = self
+            .rows
+            .get(idx + 1)
+            .filter(|next| !next.end_sequence && next.line == row.line && next.file == row.file)
+            .map(|next| next.column);
+
+        let source = if self.embed_sources {
+            self.extract_embedded_source(&row.file, row.line)
+        } else {
+            None
+        };
+
         Some(SourceLocation {
-            file: "token.rs".to_string(),
-            line: 45,
-            column: 12,
-            column_end: Some(20),
+            file: row.file.clone(),
+            line: row.line,
+            column: row.column,
+            column_end,
+            source,
         })
     }
 
+    /// Re-walks the module's line program to find the file entry matching
+    /// `file` and decode its DWARFv5 `DW_LNCT_source` attribute (if any),
+    /// then slices out a few lines of context around `line`. Only called
+    /// from `map_wasm_offset_to_source` when embedding is enabled, so we
+    /// never pay to decode a file's full embedded source unless a trace
+    /// actually mapped to it.
+    fn extract_embedded_source(&self, file: &str, line: u32) -> Option<String> {
+        const CONTEXT_LINES: usize = 2;
+
+        let obj = object::File::parse(self.wasm_bytes.as_slice()).ok()?;
+        let load_section = |id: gimli::SectionId| -> Result<Cow<[u8]>, gimli::Error> {
+            Ok(obj
+                .section_by_name(id.name())
+                .and_then(|section| section.uncompressed_data().ok())
+                .unwrap_or(Cow::Borrowed(&[])))
+        };
+        let dwarf_cow = gimli::Dwarf::load(load_section).ok()?;
+        let dwarf = dwarf_cow.borrow(|section| EndianSlice::new(section, LittleEndian));
+
+        let mut unit_headers = dwarf.units();
+        while let Ok(Some(header)) = unit_headers.next() {
+            let Ok(unit) = dwarf.unit(header) else {
+                continue;
+            };
+            let Some(line_program) = &unit.line_program else {
+                continue;
+            };
+            let header = line_program.header();
+
+            for file_entry in header.file_names() {
+                let resolved = match dwarf.attr_string(&unit, file_entry.path_name()) {
+                    Ok(s) => s.to_string_lossy().into_owned(),
+                    Err(_) => continue,
+                };
+                if resolved != file {
+                    continue;
+                }
+
+                let source_attr = file_entry.source(header)?;
+                let source_text = dwarf
+                    .attr_string(&unit, source_attr)
+                    .ok()?
+                    .to_string_lossy()
+                    .into_owned();
+
+                return Some(snippet_around_line(&source_text, line, CONTEXT_LINES));
+            }
+        }
+
+        None
+    }
+
     pub fn has_debug_symbols(&self) -> bool {
         self.has_symbols
     }
+
+    /// Resolves every offset captured from a trap message into an ordered
+    /// backtrace, so callers see a full stack instead of one opaque offset.
+    pub fn resolve_backtrace(&self, wasm_offsets: &[u64]) -> Vec<Frame> {
+        wasm_offsets
+            .iter()
+            .map(|&offset| Frame {
+                wasm_offset: offset,
+                function_name: self.resolve_function_name(offset),
+                source_location: self.map_wasm_offset_to_source(offset),
+            })
+            .collect()
+    }
+
+    /// Binary-searches the parsed `name`-section functions for the one
+    /// whose start address is the greatest `<= wasm_offset`, mirroring
+    /// `map_wasm_offset_to_source`'s lookup over line rows.
+    fn resolve_function_name(&self, wasm_offset: u64) -> Option<String> {
+        let idx = match self
+            .functions
+            .binary_search_by(|(address, _)| address.cmp(&wasm_offset))
+        {
+            Ok(i) => i,
+            Err(0) => return None,
+            Err(i) => i - 1,
+        };
+        self.functions.get(idx).map(|(_, name)| name.clone())
+    }
+
+    /// Serializes every resolved line row into a browser-compatible Source
+    /// Map v3 document, treating the WASM byte offset as the generated
+    /// position so existing devtools/sourcemap libraries can consume a
+    /// trace without the original repo.
+    pub fn to_source_map_v3(&self) -> String {
+        let mut sources: Vec<String> = Vec::new();
+        let mut mappings = String::new();
+
+        let (mut prev_gen_col, mut prev_source_index, mut prev_orig_line, mut prev_orig_col) =
+            (0i64, 0i64, 0i64, 0i64);
+
+        for (i, row) in self.rows.iter().filter(|r| !r.end_sequence).enumerate() {
+            if i > 0 {
+                mappings.push(';');
+                // Each row is emitted as its own generated line, and
+                // consumers reset `generatedColumn` to 0 at every `;`, so
+                // the first segment on the new line must carry an absolute
+                // column rather than a delta against the previous row.
+                prev_gen_col = 0;
+            }
+
+            let source_index = match sources.iter().position(|s| s == &row.file) {
+                Some(pos) => pos as i64,
+                None => {
+                    sources.push(row.file.clone());
+                    (sources.len() - 1) as i64
+                }
+            };
+            let gen_col = row.address as i64;
+            // `row.line` is DWARF's 1-based line number (0 means unknown);
+            // Source Map v3's `originalLine` is 0-based, so convert here
+            // rather than leaking the 1-based value into the mappings.
+            let orig_line = (row.line as i64 - 1).max(0);
+            let orig_col = row.column as i64;
+
+            push_vlq(&mut mappings, gen_col - prev_gen_col);
+            push_vlq(&mut mappings, source_index - prev_source_index);
+            push_vlq(&mut mappings, orig_line - prev_orig_line);
+            push_vlq(&mut mappings, orig_col - prev_orig_col);
+
+            prev_gen_col = gen_col;
+            prev_source_index = source_index;
+            prev_orig_line = orig_line;
+            prev_orig_col = orig_col;
+        }
+
+        let doc = serde_json::json!({
+            "version": 3,
+            "sources": sources,
+            "names": Vec::<String>::new(),
+            "mappings": mappings,
+        });
+        doc.to_string()
+    }
+}
+
+/// Slices out `context` lines of source on either side of `line` (1-based),
+/// joined back into a single string for display.
+fn snippet_around_line(source: &str, line: u32, context: usize) -> String {
+    if line == 0 {
+        return String::new();
+    }
+    let lines: Vec<&str> = source.lines().collect();
+    let idx = (line - 1) as usize;
+    let start = idx.saturating_sub(context);
+    let end = (idx + context + 1).min(lines.len());
+    lines.get(start..end).unwrap_or(&[]).join("\n")
+}
+
+const BASE64_VLQ_CHARS: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Appends a single base64 VLQ-encoded field to `out`: 5-bit groups, the
+/// low bit of the first group carries the sign, and the high bit of every
+/// group is the continuation flag.
+fn push_vlq(out: &mut String, value: i64) {
+    let mut value = if value < 0 {
+        ((-value) as u64) << 1 | 1
+    } else {
+        (value as u64) << 1
+    };
+    loop {
+        let mut digit = (value & 0b11111) as u8;
+        value >>= 5;
+        if value > 0 {
+            digit |= 0b100000;
+        }
+        out.push(BASE64_VLQ_CHARS[digit as usize] as char);
+        if value == 0 {
+            break;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -83,10 +418,101 @@ mod tests {
             line: 42,
             column: 10,
             column_end: Some(15),
+            source: None,
         };
 
         let json = serde_json::to_string(&location).unwrap();
         assert!(json.contains("test.rs"));
         assert!(json.contains("42"));
     }
+
+    #[test]
+    fn test_source_map_v3_resets_column_per_generated_line() {
+        let mapper = SourceMapper {
+            has_symbols: true,
+            rows: vec![
+                LineRow {
+                    address: 4,
+                    file: "lib.rs".to_string(),
+                    line: 1,
+                    column: 5,
+                    end_sequence: false,
+                },
+                LineRow {
+                    address: 10,
+                    file: "lib.rs".to_string(),
+                    line: 2,
+                    column: 3,
+                    end_sequence: false,
+                },
+            ],
+            functions: Vec::new(),
+            wasm_bytes: Vec::new(),
+            embed_sources: false,
+        };
+
+        let doc: serde_json::Value = serde_json::from_str(&mapper.to_source_map_v3()).unwrap();
+        let mappings = doc["mappings"].as_str().unwrap();
+        let mut segments = mappings.split(';');
+
+        // First segment's generated column is absolute (4), and its
+        // originalLine is the 0-based conversion of DWARF line 1, i.e. 0.
+        assert_eq!(segments.next().unwrap(), "IAAK");
+        // Second segment starts a new generated line, so its column is
+        // also absolute (10, "U") rather than a delta against the first
+        // (10-4 = 6, which would encode as "M").
+        assert_eq!(segments.next().unwrap(), "UACF");
+    }
+
+    #[test]
+    fn test_resolve_function_name_picks_containing_function() {
+        let mapper = SourceMapper {
+            has_symbols: false,
+            rows: Vec::new(),
+            functions: vec![(0, "init".to_string()), (100, "transfer".to_string())],
+            wasm_bytes: Vec::new(),
+            embed_sources: false,
+        };
+
+        assert_eq!(mapper.resolve_function_name(50), Some("init".to_string()));
+        assert_eq!(
+            mapper.resolve_function_name(150),
+            Some("transfer".to_string())
+        );
+        assert_eq!(mapper.resolve_function_name(0), Some("init".to_string()));
+    }
+
+    #[test]
+    fn test_column_end_does_not_cross_a_file_boundary() {
+        // Two compilation units whose line rows happen to share a line
+        // number (1) right at the unit boundary; `rows` is sorted by
+        // address across all units, so the next row after `a.rs`'s is
+        // `b.rs`'s despite the unrelated file.
+        let mapper = SourceMapper {
+            has_symbols: true,
+            rows: vec![
+                LineRow {
+                    address: 0,
+                    file: "a.rs".to_string(),
+                    line: 1,
+                    column: 5,
+                    end_sequence: false,
+                },
+                LineRow {
+                    address: 10,
+                    file: "b.rs".to_string(),
+                    line: 1,
+                    column: 9,
+                    end_sequence: false,
+                },
+            ],
+            functions: Vec::new(),
+            wasm_bytes: Vec::new(),
+            embed_sources: false,
+        };
+
+        let loc = mapper.map_wasm_offset_to_source(0).unwrap();
+        assert_eq!(loc.file, "a.rs");
+        assert_eq!(loc.column_end, None);
+    }
 }
@@ -0,0 +1,181 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::ResourceCalibration;
+use crate::source_mapper::{Frame, SourceLocation};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimulationRequest {
+    pub envelope_xdr: String,
+    #[serde(default)]
+    pub result_meta_xdr: String,
+    pub contract_wasm: Option<String>,
+    #[serde(default)]
+    pub ledger_entries: Option<Vec<(String, String)>>,
+    #[serde(default)]
+    pub resource_calibration: Option<ResourceCalibration>,
+    #[serde(default)]
+    pub enable_optimization_advisor: bool,
+    #[serde(default)]
+    pub profile: Option<bool>,
+    /// Opt-in to decoding DWARFv5 embedded-source snippets into resolved
+    /// `SourceLocation`s. Off by default since scanning for the attribute
+    /// is only worth the cost when a caller actually wants snippets.
+    #[serde(default)]
+    pub embed_source_snippets: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SimulationResponse {
+    pub status: String,
+    pub error: Option<String>,
+    pub events: Vec<String>,
+    pub diagnostic_events: Vec<DiagnosticEvent>,
+    pub categorized_events: Vec<CategorizedEvent>,
+    pub logs: Vec<String>,
+    pub flamegraph: Option<String>,
+    pub optimization_report: Option<crate::gas_optimizer::OptimizationReport>,
+    pub budget_usage: Option<BudgetUsage>,
+    pub source_location: Option<SourceLocation>,
+    pub wasm_offset: Option<u64>,
+    /// Raw panic-hook backtrace lines, only populated when the simulator
+    /// itself panicked (see `install_panic_hook`).
+    pub panic_backtrace: Option<Vec<String>>,
+    /// Symbolicated wasm trap backtrace, one entry per frame recovered
+    /// from the trap message and resolved against the module's debug info.
+    pub backtrace: Vec<Frame>,
+    pub host_error: Option<HostAbort>,
+    pub trap_code: Option<TrapCode>,
+}
+
+/// A host-signalled abort recovered from the `Box<dyn Any + Send>` payload
+/// a host function unwound with (caught by `catch_unwind`) and downcast
+/// into a known shape, so callers can distinguish an abort (with its code
+/// or message) from an unexpected VM trap.
+#[derive(Debug, Clone, Serialize)]
+pub struct HostAbort {
+    pub code: Option<i32>,
+    pub type_name: String,
+    pub message: Option<String>,
+}
+
+/// Payload a host function can abort with to signal a specific exit code,
+/// rather than an arbitrary panic string.
+#[derive(Debug, Clone)]
+pub struct ExitCode(pub i32);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetUsage {
+    pub cpu_instructions: u64,
+    pub memory_bytes: u64,
+    pub operations_count: usize,
+    pub cpu_limit: u64,
+    pub memory_limit: u64,
+    pub cpu_usage_percent: f64,
+    pub memory_usage_percent: f64,
+    pub per_cost_type: Vec<CostTypeUsage>,
+    /// Best-effort process RSS delta (in 4 KiB pages) across dropping this
+    /// run's `Host` and trimming the allocator, via `runner::decommit_memory`.
+    /// `Host` doesn't expose its instance's linear memory for a targeted
+    /// `madvise`, and this is a whole-process measurement, so it's noisy
+    /// under concurrent allocations -- treat it as a rough signal of memory
+    /// behavior per run in daemon mode, not an exact linear-memory count.
+    pub reclaimed_pages: u64,
+}
+
+/// Resource consumption attributed to a single `ContractCostType`, so a
+/// caller can see which metered operation actually dominated the run
+/// instead of just the aggregate totals.
+#[derive(Debug, Clone, Serialize)]
+pub struct CostTypeUsage {
+    pub name: String,
+    pub cpu_insns: u64,
+    pub mem_bytes: u64,
+    pub iterations: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticEvent {
+    pub event_type: String,
+    pub contract_id: Option<String>,
+    pub topics: Vec<String>,
+    pub data: String,
+    pub in_successful_contract_call: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategorizedEvent {
+    pub category: String,
+    pub event: DiagnosticEvent,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StructuredError {
+    pub error_type: String,
+    pub message: String,
+    pub details: Option<String>,
+}
+
+/// A stable, machine-consumable classification of a wasm trap, so clients
+/// can branch on the trap class instead of pattern-matching the host's
+/// debug-formatted error string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TrapCode {
+    OutOfBoundsMemoryAccess,
+    UndefinedElement,
+    IndirectCallTypeMismatch,
+    IntegerOverflow,
+    IntegerDivByZero,
+    InvalidConversionToInt,
+    UnreachableReached,
+    StackExhaustion,
+    BudgetExceeded,
+    Unknown,
+}
+
+impl std::fmt::Display for TrapCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            TrapCode::OutOfBoundsMemoryAccess => "VM Trap: Out of Bounds Access",
+            TrapCode::UndefinedElement => "VM Trap: Undefined Element",
+            TrapCode::IndirectCallTypeMismatch => "VM Trap: Indirect Call Type Mismatch",
+            TrapCode::IntegerOverflow => "VM Trap: Integer Overflow",
+            TrapCode::IntegerDivByZero => "VM Trap: Integer Divide By Zero",
+            TrapCode::InvalidConversionToInt => "VM Trap: Invalid Conversion To Integer",
+            TrapCode::UnreachableReached => "VM Trap: Unreachable Executed",
+            TrapCode::StackExhaustion => "VM Trap: Stack Exhausted",
+            TrapCode::BudgetExceeded => "VM Trap: Budget Exceeded",
+            TrapCode::Unknown => "Unknown Error",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Classifies a raw host-error message into a [`TrapCode`]. This still
+/// relies on substring matching against the known wasmtime trap messages
+/// (the host only gives us a debug-formatted string), but callers now get
+/// a stable enum to branch on instead of parsing the rendered text.
+pub fn decode_error(raw: &str) -> TrapCode {
+    if raw.contains("out of bounds memory access") {
+        TrapCode::OutOfBoundsMemoryAccess
+    } else if raw.contains("undefined element") {
+        TrapCode::UndefinedElement
+    } else if raw.contains("indirect call type mismatch") {
+        TrapCode::IndirectCallTypeMismatch
+    } else if raw.contains("integer overflow") {
+        TrapCode::IntegerOverflow
+    } else if raw.contains("integer divide by zero") {
+        TrapCode::IntegerDivByZero
+    } else if raw.contains("invalid conversion to integer") {
+        TrapCode::InvalidConversionToInt
+    } else if raw.contains("unreachable") {
+        TrapCode::UnreachableReached
+    } else if raw.contains("call stack exhausted") {
+        TrapCode::StackExhaustion
+    } else if raw.contains("budget") || raw.contains("resource limit exceeded") {
+        TrapCode::BudgetExceeded
+    } else {
+        TrapCode::Unknown
+    }
+}
@@ -1,22 +1,68 @@
 // Copyright 2025 Erst Users
 // SPDX-License-Identifier: Apache-2.0
 
+mod services;
 
-let cli = Cli::parse();
+use clap::Parser;
+use services::{
+    resolve_uploader,
+    share::{canonicalize_upload_url, sign_trace, verify_trace},
+    AppError, Cli,
+};
 
-let trace_json = generate_trace();
-
-if cli.share {
-    let uploader = GistUploader::new(token);
-    let url = uploader.upload(&trace_json, cli.public)?;
-    println!("Shared: {}", url);
+/// Reads the trace JSON to share from stdin. This crate has no dependency
+/// on the simulator's types, so rather than re-deriving a trace here, a
+/// caller pipes in whatever `SimulationResponse` JSON the simulator
+/// already emitted for the run being shared.
+fn generate_trace() -> Result<String, AppError> {
+    use std::io::Read;
+    let mut trace_json = String::new();
+    std::io::stdin().read_to_string(&mut trace_json)?;
+    let trace_json = trace_json.trim().to_string();
+    if trace_json.is_empty() {
+        return Err(AppError::Io(
+            "no trace JSON provided on stdin; pipe in the simulator's output".to_string(),
+        ));
+    }
+    Ok(trace_json)
 }
-let cli = Cli::parse();
 
-let trace_json = generate_trace();
+fn main() -> Result<(), AppError> {
+    let cli = Cli::parse();
+
+    if let Some(token) = &cli.verify {
+        let key_path = cli.verify_key.as_ref().ok_or_else(|| {
+            AppError::Verification("--verify-key is required with --verify".to_string())
+        })?;
+        let public_key_pem = std::fs::read_to_string(key_path)?;
+        let trace_json = verify_trace(&public_key_pem, token)?;
+        println!("{}", trace_json);
+        return Ok(());
+    }
+
+    if cli.share {
+        let trace_json = generate_trace()?;
+
+        // Signing is opt-in: when a key is supplied, what actually gets
+        // uploaded is the signed JWT rather than the raw trace JSON, so a
+        // recipient can verify the shared link wasn't tampered with.
+        let (payload, signed_token) = match &cli.sign_key {
+            Some(key_path) => {
+                let key_raw = std::fs::read_to_string(key_path)?;
+                let token = sign_trace(&trace_json, &key_raw)?;
+                (token.clone(), Some(token))
+            }
+            None => (trace_json, None),
+        };
+
+        let uploader = resolve_uploader(cli.backend, &cli)?;
+        let url = uploader.upload(&payload, cli.public)?;
+        println!("Shared: {}", canonicalize_upload_url(&url));
+
+        if let Some(token) = signed_token {
+            println!("Signed trace (JWT): {}", token);
+        }
+    }
 
-if cli.share {
-    let uploader = GistUploader::new(token);
-    let url = uploader.upload(&trace_json, cli.public)?;
-    println!("Shared: {}", url);
+    Ok(())
 }
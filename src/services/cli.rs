@@ -1,6 +1,7 @@
 // Copyright 2025 Erst Users
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::services::share::Backend;
 use clap::Parser;
 
 #[derive(Parser, Debug)]
@@ -10,4 +11,36 @@ pub struct Cli {
 
     #[arg(long)]
     pub public: bool,
+
+    /// Which sharing backend to upload the trace to.
+    #[arg(long, value_enum, default_value = "gist")]
+    pub backend: Backend,
+
+    /// GitHub token, required when `--backend gist` is selected.
+    #[arg(long)]
+    pub gist_token: Option<String>,
+
+    /// Base URL to PUT the trace to, required when `--backend http` is selected.
+    #[arg(long)]
+    pub upload_url: Option<String>,
+
+    /// Directory to write the trace to, used by `--backend local-file`
+    /// (defaults to `./traces`).
+    #[arg(long)]
+    pub output_dir: Option<String>,
+
+    /// Path to an RSA signing key (PEM or JWK JSON). When set, the trace is
+    /// wrapped in a signed JWT before upload so the shared link can be
+    /// verified as unmodified.
+    #[arg(long)]
+    pub sign_key: Option<String>,
+
+    /// Verify a previously signed trace JWT instead of sharing a new one.
+    /// Takes the token itself; pair with `--verify-key`.
+    #[arg(long)]
+    pub verify: Option<String>,
+
+    /// Path to the RSA public key (PEM) used to check `--verify`.
+    #[arg(long)]
+    pub verify_key: Option<String>,
 }
@@ -0,0 +1,37 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+use std::fmt;
+
+/// Top-level error type for the erst CLI's trace-sharing pipeline.
+#[derive(Debug)]
+pub enum AppError {
+    /// A backend failed to accept or store the uploaded trace.
+    Upload(String),
+    /// Reading or writing local state (e.g. the local-file backend) failed.
+    Io(String),
+    /// The signing key was unusable, or the JWS couldn't be produced.
+    Signing(String),
+    /// A signed trace failed verification: bad signature or content-hash
+    /// mismatch.
+    Verification(String),
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Upload(msg) => write!(f, "upload failed: {}", msg),
+            AppError::Io(msg) => write!(f, "io error: {}", msg),
+            AppError::Signing(msg) => write!(f, "signing failed: {}", msg),
+            AppError::Verification(msg) => write!(f, "verification failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<std::io::Error> for AppError {
+    fn from(err: std::io::Error) -> Self {
+        AppError::Io(err.to_string())
+    }
+}
@@ -1,8 +1,10 @@
 // Copyright 2025 Erst Users
 // SPDX-License-Identifier: Apache-2.0
 
-pub mod uploader;
-pub mod gist;
+pub mod cli;
+pub mod error;
+pub mod share;
 
-pub use uploader::TraceUploader;
-pub use gist::GistUploader;
+pub use cli::Cli;
+pub use error::AppError;
+pub use share::{resolve_uploader, Backend, GistUploader, TraceUploader};
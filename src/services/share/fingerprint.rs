@@ -0,0 +1,21 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+use siphasher::sip::SipHasher13;
+use std::hash::Hasher;
+
+/// A fixed key, so the fingerprint is reproducible across processes and
+/// runs. This is a content identifier, not a MAC — a public constant key
+/// is correct here since nothing depends on the key being secret.
+const FINGERPRINT_KEYS: (u64, u64) = (0x65727374_74726163, 0x66696e67_65727074);
+
+/// Computes a short, deterministic identifier for trace content: a 64-bit
+/// SipHash over the JSON bytes, rendered as 16 lowercase hex characters.
+/// Used as the trace's stable ID and default upload filename/slug, so
+/// re-uploading identical content is idempotent and the returned link is
+/// reproducible.
+pub fn fingerprint_hex(content: &[u8]) -> String {
+    let mut hasher = SipHasher13::new_with_keys(FINGERPRINT_KEYS.0, FINGERPRINT_KEYS.1);
+    hasher.write(content);
+    format!("{:016x}", hasher.finish())
+}
@@ -1,6 +1,10 @@
 // Copyright 2025 Erst Users
 // SPDX-License-Identifier: Apache-2.0
 
+use super::fingerprint::fingerprint_hex;
+use super::uploader::TraceUploader;
+use crate::services::error::AppError;
+
 pub struct GistUploader {
     token: String,
 }
@@ -12,10 +16,29 @@ impl GistUploader {
 }
 
 impl TraceUploader for GistUploader {
-    fn upload(...) -> Result<String, AppError> {
-        // build request
-        // send HTTP
-        // parse URL
-        // return link
+    fn upload(&self, content: &str, public: bool) -> Result<String, AppError> {
+        let filename = format!("trace-{}.json", fingerprint_hex(content.as_bytes()));
+
+        let body = serde_json::json!({
+            "description": "erst trace",
+            "public": public,
+            "files": { filename: { "content": content } },
+        });
+
+        let response = ureq::post("https://api.github.com/gists")
+            .set("Authorization", &format!("token {}", self.token))
+            .set("User-Agent", "erst")
+            .send_json(body)
+            .map_err(|e| AppError::Upload(format!("gist request failed: {}", e)))?;
+
+        let parsed: serde_json::Value = response
+            .into_json()
+            .map_err(|e| AppError::Upload(format!("gist response was not JSON: {}", e)))?;
+
+        parsed
+            .get("html_url")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+            .ok_or_else(|| AppError::Upload("gist response missing html_url".to_string()))
     }
 }
@@ -0,0 +1,33 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+use super::fingerprint::fingerprint_hex;
+use super::uploader::TraceUploader;
+use crate::services::error::AppError;
+
+/// Uploads a trace to a plain HTTP endpoint via `PUT`, addressing it by its
+/// content fingerprint so identical traces overwrite the same object
+/// instead of accumulating duplicates.
+pub struct HttpPutUploader {
+    base_url: String,
+}
+
+impl HttpPutUploader {
+    pub fn new(base_url: String) -> Self {
+        Self { base_url }
+    }
+}
+
+impl TraceUploader for HttpPutUploader {
+    fn upload(&self, content: &str, _public: bool) -> Result<String, AppError> {
+        let ident = fingerprint_hex(content.as_bytes());
+        let url = format!("{}/{}.json", self.base_url.trim_end_matches('/'), ident);
+
+        ureq::put(&url)
+            .set("Content-Type", "application/json")
+            .send_string(content)
+            .map_err(|e| AppError::Upload(format!("PUT {} failed: {}", url, e)))?;
+
+        Ok(url)
+    }
+}
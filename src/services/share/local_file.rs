@@ -0,0 +1,33 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+use super::fingerprint::fingerprint_hex;
+use super::uploader::TraceUploader;
+use crate::services::error::AppError;
+use std::fs;
+use std::path::PathBuf;
+
+/// Writes a trace to a file on disk instead of a remote service, for local
+/// testing or air-gapped use. The fingerprint is the filename, so writing
+/// the same trace twice is a no-op rewrite rather than a new file.
+pub struct LocalFileUploader {
+    output_dir: PathBuf,
+}
+
+impl LocalFileUploader {
+    pub fn new(output_dir: PathBuf) -> Self {
+        Self { output_dir }
+    }
+}
+
+impl TraceUploader for LocalFileUploader {
+    fn upload(&self, content: &str, _public: bool) -> Result<String, AppError> {
+        fs::create_dir_all(&self.output_dir)?;
+
+        let ident = fingerprint_hex(content.as_bytes());
+        let path = self.output_dir.join(format!("{}.json", ident));
+        fs::write(&path, content)?;
+
+        Ok(format!("file://{}", path.display()))
+    }
+}
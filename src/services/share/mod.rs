@@ -0,0 +1,18 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+mod fingerprint;
+mod gist;
+mod http_put;
+mod local_file;
+mod registry;
+mod signing;
+mod uploader;
+
+pub use fingerprint::fingerprint_hex;
+pub use gist::GistUploader;
+pub use http_put::HttpPutUploader;
+pub use local_file::LocalFileUploader;
+pub use registry::{resolve_uploader, Backend};
+pub use signing::{sign_trace, verify_trace};
+pub use uploader::{canonicalize_upload_url, TraceUploader};
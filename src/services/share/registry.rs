@@ -0,0 +1,48 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+use super::gist::GistUploader;
+use super::http_put::HttpPutUploader;
+use super::local_file::LocalFileUploader;
+use super::uploader::TraceUploader;
+use crate::services::cli::Cli;
+use crate::services::error::AppError;
+use std::path::PathBuf;
+
+/// The sharing backends `--backend` can select between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Backend {
+    Gist,
+    Http,
+    LocalFile,
+}
+
+/// Resolves the CLI's chosen backend into a concrete [`TraceUploader`],
+/// pulling whatever per-backend configuration it needs (token, upload URL,
+/// output directory) out of the parsed `Cli`.
+pub fn resolve_uploader(backend: Backend, cli: &Cli) -> Result<Box<dyn TraceUploader>, AppError> {
+    match backend {
+        Backend::Gist => {
+            let token = cli
+                .gist_token
+                .clone()
+                .ok_or_else(|| AppError::Upload("--gist-token is required for the gist backend".to_string()))?;
+            Ok(Box::new(GistUploader::new(token)))
+        }
+        Backend::Http => {
+            let base_url = cli
+                .upload_url
+                .clone()
+                .ok_or_else(|| AppError::Upload("--upload-url is required for the http backend".to_string()))?;
+            Ok(Box::new(HttpPutUploader::new(base_url)))
+        }
+        Backend::LocalFile => {
+            let output_dir = cli
+                .output_dir
+                .clone()
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("./traces"));
+            Ok(Box::new(LocalFileUploader::new(output_dir)))
+        }
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright 2025 Erst Users
+// SPDX-License-Identifier: Apache-2.0
+
+use super::fingerprint::fingerprint_hex;
+use crate::services::error::AppError;
+use base64::Engine as _;
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use rsa::pkcs1::EncodeRsaPrivateKey;
+use rsa::RsaPrivateKey;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Claims embedded in a signed trace: the trace JSON itself plus enough
+/// metadata (`iat`, a content hash) to confirm nothing was tampered with
+/// in transit.
+#[derive(Debug, Serialize, Deserialize)]
+struct TraceClaims {
+    trace: String,
+    iat: u64,
+    content_hash: String,
+}
+
+/// A JWK's RSA private-key components, for the JSON input form. PEM input
+/// skips this entirely since `jsonwebtoken` parses PEM directly.
+#[derive(Debug, Deserialize)]
+struct RsaJwk {
+    n: String,
+    e: String,
+    d: String,
+    p: String,
+    q: String,
+}
+
+fn b64url_to_uint(field: &str) -> Result<rsa::BigUint, AppError> {
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(field)
+        .map_err(|e| AppError::Signing(format!("invalid JWK field: {}", e)))?;
+    Ok(rsa::BigUint::from_bytes_be(&bytes))
+}
+
+/// Loads a signing key from either PEM or JWK JSON, converting a JWK's RSA
+/// components to DER (via PKCS#1) so we can hand `jsonwebtoken` the same
+/// DER-encoded key it would get from a PEM file.
+fn load_encoding_key(raw: &str) -> Result<EncodingKey, AppError> {
+    let trimmed = raw.trim_start();
+    if trimmed.starts_with("-----BEGIN") {
+        return EncodingKey::from_rsa_pem(raw.as_bytes())
+            .map_err(|e| AppError::Signing(format!("invalid PEM signing key: {}", e)));
+    }
+
+    let jwk: RsaJwk = serde_json::from_str(raw)
+        .map_err(|e| AppError::Signing(format!("invalid JWK signing key: {}", e)))?;
+    let key = RsaPrivateKey::from_components(
+        b64url_to_uint(&jwk.n)?,
+        b64url_to_uint(&jwk.e)?,
+        b64url_to_uint(&jwk.d)?,
+        vec![b64url_to_uint(&jwk.p)?, b64url_to_uint(&jwk.q)?],
+    )
+    .map_err(|e| AppError::Signing(format!("invalid RSA key components: {}", e)))?;
+
+    let der = key
+        .to_pkcs1_der()
+        .map_err(|e| AppError::Signing(format!("failed to encode RSA key as DER: {}", e)))?;
+    Ok(EncodingKey::from_rsa_der(der.as_bytes()))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Wraps `trace_json` in a compact RS256 JWS: the trace itself as a claim,
+/// an `iat`, and a content hash of the trace so a verifier can catch a
+/// tampered claim even if the signature check were somehow bypassed.
+pub fn sign_trace(trace_json: &str, key_raw: &str) -> Result<String, AppError> {
+    let encoding_key = load_encoding_key(key_raw)?;
+    let claims = TraceClaims {
+        trace: trace_json.to_string(),
+        iat: unix_now(),
+        content_hash: fingerprint_hex(trace_json.as_bytes()),
+    };
+    encode(&Header::new(Algorithm::RS256), &claims, &encoding_key)
+        .map_err(|e| AppError::Signing(format!("failed to sign trace: {}", e)))
+}
+
+/// Verifies a compact JWS produced by [`sign_trace`] against the given RSA
+/// public key (PEM), confirming both the signature and that the embedded
+/// content hash matches the embedded trace. Returns the trace JSON on
+/// success.
+pub fn verify_trace(public_key_pem: &str, token: &str) -> Result<String, AppError> {
+    let decoding_key = DecodingKey::from_rsa_pem(public_key_pem.as_bytes())
+        .map_err(|e| AppError::Verification(format!("invalid public key: {}", e)))?;
+
+    let mut validation = Validation::new(Algorithm::RS256);
+    validation.validate_exp = false;
+    // `TraceClaims` carries no `exp`, so don't require one.
+    validation.required_spec_claims.clear();
+
+    let data = decode::<TraceClaims>(token, &decoding_key, &validation)
+        .map_err(|e| AppError::Verification(format!("signature invalid: {}", e)))?;
+
+    let expected_hash = fingerprint_hex(data.claims.trace.as_bytes());
+    if expected_hash != data.claims.content_hash {
+        return Err(AppError::Verification(
+            "content hash does not match signed trace".to_string(),
+        ));
+    }
+
+    Ok(data.claims.trace)
+}
@@ -1,6 +1,74 @@
 // Copyright 2025 Erst Users
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::services::error::AppError;
+
+/// A named destination a trace can be shared to. Every backend gets the
+/// same trace JSON and the same `public` flag; it's free to ignore
+/// `public` if the concept doesn't apply (e.g. the local-file backend).
 pub trait TraceUploader {
     fn upload(&self, content: &str, public: bool) -> Result<String, AppError>;
 }
+
+/// Normalizes an upload URL before it's printed, so `Shared: ...` output
+/// looks the same regardless of which backend produced the link: lowercase
+/// the host, strip a default port for the scheme, and use the last path
+/// segment as the short ident.
+pub fn canonicalize_upload_url(url: &str) -> String {
+    let (scheme, rest) = match url.split_once("://") {
+        Some(parts) => parts,
+        None => return url.to_string(),
+    };
+
+    // `file` URLs have no host/port to normalize, and the path after the
+    // scheme *is* the location, not a path to shorten to its last segment
+    // -- collapsing it would print a `Shared:` link that no longer points
+    // at the file the local-file backend actually wrote.
+    if scheme == "file" {
+        return url.to_string();
+    }
+
+    let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let (host, port) = authority.split_once(':').unwrap_or((authority, ""));
+    let host = host.to_lowercase();
+
+    let default_port = match scheme {
+        "http" => "80",
+        "https" => "443",
+        _ => "",
+    };
+    let authority = if port.is_empty() || port == default_port {
+        host
+    } else {
+        format!("{}:{}", host, port)
+    };
+
+    let ident = path.rsplit('/').next().unwrap_or(path);
+    format!("{}://{}/{}", scheme, authority, ident)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonicalize_strips_default_port_and_lowercases_host() {
+        let canonical = canonicalize_upload_url("https://Gist.GitHub.com:443/path/to/abc123");
+        assert_eq!(canonical, "https://gist.github.com/abc123");
+    }
+
+    #[test]
+    fn canonicalize_keeps_non_default_port() {
+        let canonical = canonicalize_upload_url("http://example.com:8080/traces/abc123");
+        assert_eq!(canonical, "http://example.com:8080/abc123");
+    }
+
+    #[test]
+    fn canonicalize_leaves_file_urls_untouched() {
+        let canonical = canonicalize_upload_url("file://./traces/abc123.json");
+        assert_eq!(canonical, "file://./traces/abc123.json");
+
+        let canonical = canonicalize_upload_url("file:///var/traces/abc123.json");
+        assert_eq!(canonical, "file:///var/traces/abc123.json");
+    }
+}